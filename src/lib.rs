@@ -0,0 +1,722 @@
+use std::borrow::Cow;
+
+/// Incremental re-lexing over a [`ropey::Rope`], for editor/LSP use.
+#[cfg(feature = "rope")]
+pub mod rope;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token<'src> {
+    // Keywords
+    Auto,
+    Break,
+    Case,
+    Char,
+    Const,
+    Continue,
+    Default,
+    Do,
+    Double,
+    Else,
+    Enum,
+    Extern,
+    Float,
+    For,
+    Goto,
+    If,
+    Int,
+    Long,
+    Register,
+    Return,
+    Short,
+    Signed,
+    Sizeof,
+    Static,
+    Struct,
+    Switch,
+    Typedef,
+    Union,
+    Unsigned,
+    Void,
+    Volatile,
+    While,
+
+    // Identifiers and literals
+    Identifier(&'src str),
+    /// An integer constant, carrying its `u`/`l`/`ll` suffix so a later
+    /// parser can assign the correct C type instead of truncating it.
+    Number {
+        value: u64,
+        is_unsigned: bool,
+        width: IntWidth,
+    },
+    FloatLiteral(f64),
+    CharLiteral(char),
+    /// `has_escape` is set when the source used at least one `\` escape, so
+    /// a pretty-printer can tell a decoded value from a literal slice.
+    String {
+        value: Cow<'src, str>,
+        has_escape: bool,
+    },
+
+    // Operators and punctuation
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Assign,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    PercentAssign,
+    Increment,
+    Decrement,
+    Equals,
+    NotEquals,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    LogicalAnd,
+    LogicalOr,
+    LogicalNot,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseNot,
+    LeftShift,
+    RightShift,
+
+    // Punctuation
+    Semicolon,
+    Comma,
+    Dot,
+    Arrow,
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Question,
+    Colon,
+
+    // Special tokens
+    EOF,
+}
+
+/// The width implied by an integer literal's `l`/`ll` suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntWidth {
+    Int,
+    Long,
+    LongLong,
+}
+
+/// A half-open `[start, end)` byte-offset range into the lexer's input.
+pub type Span = (usize, usize);
+
+/// The kind of problem a [`LexError`] reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    UnterminatedComment,
+    UnterminatedString,
+    InvalidNumber,
+}
+
+/// A non-fatal lexing diagnostic: something was wrong at `span`, but the
+/// lexer recovered and kept producing tokens for the rest of the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+/// Lex the entire `input`, returning every token paired with its source span
+/// together with any diagnostics collected along the way. A non-empty error
+/// list does not mean the token list is unusable: the lexer skips past each
+/// problem and keeps going, so callers can report every issue in one pass
+/// instead of stopping at the first one.
+pub fn lex(input: &str) -> (Vec<(Token<'_>, Span)>, Vec<LexError>) {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    loop {
+        let (token, span) = lexer.next_token();
+        let is_eof = token == Token::EOF;
+        tokens.push((token, span));
+        if is_eof {
+            break;
+        }
+    }
+
+    (tokens, lexer.errors().to_vec())
+}
+
+#[derive(Default)]
+pub struct SymbolTable<'src> {
+    symbols: Vec<&'src str>,
+}
+
+impl<'src> SymbolTable<'src> {
+    pub fn new() -> Self {
+        SymbolTable {
+            symbols: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, identifier: &'src str) -> usize {
+        if let Some(i) = self.symbols.iter().position(|&name| name == identifier) {
+            return i;
+        }
+        self.symbols.push(identifier);
+        self.symbols.len() - 1
+    }
+
+    pub fn names(&self) -> &[&'src str] {
+        &self.symbols
+    }
+}
+
+pub struct Lexer<'src> {
+    input: &'src str,
+    position: usize,
+    symbol_table: SymbolTable<'src>,
+    errors: Vec<LexError>,
+}
+
+impl<'src> Lexer<'src> {
+    pub fn new(input: &'src str) -> Self {
+        Lexer {
+            input,
+            position: 0,
+            symbol_table: SymbolTable::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn symbol_table(&self) -> &SymbolTable<'src> {
+        &self.symbol_table
+    }
+
+    /// Diagnostics collected so far. Populated as `next_token` recovers from
+    /// problems in the input; never cleared.
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
+
+    /// Scan and return the next token along with the byte-offset span it
+    /// occupies in the original input.
+    pub fn next_token(&mut self) -> (Token<'src>, Span) {
+        self.skip_whitespace();
+        self.skip_preprocessor_line();
+
+        let start = self.position;
+        let remaining = &self.input[self.position..];
+        if remaining.is_empty() {
+            return (Token::EOF, (start, start));
+        }
+
+        // Check for preprocessor line at the current position
+        if remaining.starts_with('#') {
+            self.skip_preprocessor_line();
+            return self.next_token();
+        }
+
+        // Check for multiline comments
+        if remaining.starts_with("/*") {
+            if !self.skip_until("/*", "*/") {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::UnterminatedComment,
+                    span: (start, self.position),
+                });
+            }
+            return self.next_token(); // Restart tokenization after skipping
+        }
+
+        if remaining.starts_with("//") {
+            self.skip_until("//", "\n");
+            return self.next_token();
+        }
+
+        // Match string and character literals
+        if remaining.starts_with('"') {
+            self.position += 1;
+            let (value, has_escape, terminated) = self.scan_quoted('"');
+            if !terminated {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::UnterminatedString,
+                    span: (start, self.position),
+                });
+            }
+            return (Token::String { value, has_escape }, (start, self.position));
+        }
+        if remaining.starts_with('\'') {
+            self.position += 1;
+            let (value, _has_escape, terminated) = self.scan_quoted('\'');
+            if !terminated {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::UnterminatedString,
+                    span: (start, self.position),
+                });
+            }
+            let ch = value.chars().next().unwrap_or('\0');
+            return (Token::CharLiteral(ch), (start, self.position));
+        }
+
+        let first = remaining.chars().next().unwrap();
+        let starts_float = first == '.'
+            && remaining[1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit());
+
+        // Numbers and floats are dispatched on the first byte; a leading "."
+        // only counts as the start of a float if a digit follows it,
+        // otherwise it falls through to the Dot operator below.
+        let token = if first.is_ascii_digit() || starts_float {
+            match self.scan_number(start) {
+                Some(token) => token,
+                None => return self.next_token(),
+            }
+        } else if first.is_ascii_alphabetic() || first == '_' {
+            self.scan_identifier()
+        } else if let Some(token) = self.scan_operator() {
+            token
+        }
+        // Handle unrecognized characters
+        else {
+            self.position += first.len_utf8();
+            self.errors.push(LexError {
+                kind: LexErrorKind::UnexpectedChar(first),
+                span: (start, self.position),
+            });
+            return self.next_token();
+        };
+
+        (token, (start, self.position))
+    }
+
+    /// Line number (1-based) that a byte offset falls on, derived from the
+    /// newlines preceding it in the original input.
+    pub fn line_at(&self, offset: usize) -> usize {
+        self.input[..offset].chars().filter(|&c| c == '\n').count() + 1
+    }
+
+    /// Skip from `start` to the matching `end`, returning `false` if EOF was
+    /// reached before `end` was found.
+    fn skip_until(&mut self, start: &str, end: &str) -> bool {
+        if self.input[self.position..].starts_with(start) {
+            self.position += start.len(); // Skip the starting pattern
+
+            while self.position < self.input.len() {
+                if self.input[self.position..].starts_with(end) {
+                    self.position += end.len(); // Skip the ending pattern
+                    return true;
+                }
+
+                self.position += self.peek_char().map_or(1, char::len_utf8);
+            }
+
+            // Reached EOF before finding the end pattern.
+            return false;
+        }
+        true
+    }
+
+    /// Scan the body of a `"..."` or `'...'` literal whose opening quote has
+    /// already been consumed, decoding escape sequences as it goes. Returns
+    /// the decoded value (borrowed from `input` when no escape was seen, so
+    /// plain literals stay zero-copy), whether an escape was present, and
+    /// whether the closing `quote` was found before EOF.
+    fn scan_quoted(&mut self, quote: char) -> (Cow<'src, str>, bool, bool) {
+        let content_start = self.position;
+        let mut owned: Option<String> = None;
+
+        loop {
+            let Some(ch) = self.input[self.position..].chars().next() else {
+                let has_escape = owned.is_some();
+                let value = match owned {
+                    Some(s) => Cow::Owned(s),
+                    None => Cow::Borrowed(&self.input[content_start..self.position]),
+                };
+                return (value, has_escape, false);
+            };
+
+            if ch == quote {
+                let has_escape = owned.is_some();
+                let value = match owned {
+                    Some(s) => Cow::Owned(s),
+                    None => Cow::Borrowed(&self.input[content_start..self.position]),
+                };
+                self.position += ch.len_utf8();
+                return (value, has_escape, true);
+            }
+
+            if ch == '\\' {
+                let buf = owned
+                    .get_or_insert_with(|| self.input[content_start..self.position].to_string());
+                self.position += 1; // consume the backslash
+
+                match self.input[self.position..].chars().next() {
+                    Some('n') => {
+                        buf.push('\n');
+                        self.position += 1;
+                    }
+                    Some('t') => {
+                        buf.push('\t');
+                        self.position += 1;
+                    }
+                    Some('r') => {
+                        buf.push('\r');
+                        self.position += 1;
+                    }
+                    Some('\\') => {
+                        buf.push('\\');
+                        self.position += 1;
+                    }
+                    Some('"') => {
+                        buf.push('"');
+                        self.position += 1;
+                    }
+                    Some('\'') => {
+                        buf.push('\'');
+                        self.position += 1;
+                    }
+                    Some('x') => {
+                        self.position += 1;
+                        let hex_start = self.position;
+                        while self.position - hex_start < 2
+                            && self.input[self.position..]
+                                .chars()
+                                .next()
+                                .is_some_and(|c| c.is_ascii_hexdigit())
+                        {
+                            self.position += 1;
+                        }
+                        if let Ok(byte) =
+                            u8::from_str_radix(&self.input[hex_start..self.position], 16)
+                        {
+                            buf.push(byte as char);
+                        }
+                    }
+                    Some('0'..='7') => {
+                        let oct_start = self.position;
+                        while self.position - oct_start < 3
+                            && self.input[self.position..]
+                                .chars()
+                                .next()
+                                .is_some_and(|c| ('0'..='7').contains(&c))
+                        {
+                            self.position += 1;
+                        }
+                        if let Ok(byte) =
+                            u8::from_str_radix(&self.input[oct_start..self.position], 8)
+                        {
+                            buf.push(byte as char);
+                        }
+                    }
+                    Some(other) => {
+                        buf.push(other);
+                        self.position += other.len_utf8();
+                    }
+                    None => {}
+                }
+                continue;
+            }
+
+            if let Some(buf) = owned.as_mut() {
+                buf.push(ch);
+            }
+            self.position += ch.len_utf8();
+        }
+    }
+
+    fn skip_preprocessor_line(&mut self) {
+        while self.input[self.position..].starts_with('#') {
+            let rest = &self.input[self.position..];
+            let line_len = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            self.position += line_len;
+            self.skip_whitespace();
+        }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn consume_while(&mut self, pred: impl Fn(char) -> bool) {
+        while self.peek_char().is_some_and(&pred) {
+            self.position += 1;
+        }
+    }
+
+    /// Whether a valid float exponent (`[eE][+-]?[0-9]+`) starts at byte
+    /// offset `at`, without consuming anything.
+    fn exponent_is_valid(&self, at: usize) -> bool {
+        let mut rest = self.input[at..].chars();
+        if !matches!(rest.next(), Some('e') | Some('E')) {
+            return false;
+        }
+        let mut rest = rest.as_str();
+        if matches!(rest.chars().next(), Some('+') | Some('-')) {
+            rest = &rest[1..];
+        }
+        rest.chars().next().is_some_and(|c| c.is_ascii_digit())
+    }
+
+    /// Scan a number starting at `start`, dispatching between the integer
+    /// and floating-point grammars based on what follows the leading digits
+    /// (or, for a leading `.`, the digit that follows it).
+    fn scan_number(&mut self, start: usize) -> Option<Token<'src>> {
+        if self.input[self.position..].starts_with("0x")
+            || self.input[self.position..].starts_with("0X")
+        {
+            self.position += 2;
+            let digits_start = self.position;
+            self.consume_while(|c| c.is_ascii_hexdigit());
+            return self.finish_integer(start, digits_start, 16);
+        }
+
+        let digits_start = self.position;
+        self.consume_while(|c| c.is_ascii_digit());
+
+        let mut is_float = false;
+        if self.peek_char() == Some('.') {
+            is_float = true;
+            self.position += 1;
+            self.consume_while(|c| c.is_ascii_digit());
+        }
+        if self.exponent_is_valid(self.position) {
+            is_float = true;
+            self.position += 1; // the 'e'/'E' itself
+            if matches!(self.peek_char(), Some('+') | Some('-')) {
+                self.position += 1;
+            }
+            self.consume_while(|c| c.is_ascii_digit());
+        }
+
+        if is_float {
+            self.finish_float(start)
+        } else {
+            let digits = &self.input[digits_start..self.position];
+            if digits.len() > 1 && digits.starts_with('0') {
+                self.finish_integer(start, digits_start + 1, 8)
+            } else {
+                self.finish_integer(start, digits_start, 10)
+            }
+        }
+    }
+
+    /// Parse `self.input[start..self.position]` (already scanned as a float,
+    /// including any `.`/exponent) as an `f64`, consuming a trailing
+    /// `f`/`F`/`l`/`L` suffix first.
+    fn finish_float(&mut self, start: usize) -> Option<Token<'src>> {
+        let text = &self.input[start..self.position];
+        if matches!(
+            self.peek_char(),
+            Some('f') | Some('F') | Some('l') | Some('L')
+        ) {
+            self.position += 1;
+        }
+        match text.parse::<f64>() {
+            Ok(value) => Some(Token::FloatLiteral(value)),
+            Err(_) => {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::InvalidNumber,
+                    span: (start, self.position),
+                });
+                None
+            }
+        }
+    }
+
+    /// Consume a trailing integer suffix (`u`/`U`, `l`/`L`, combined) after
+    /// the digit run `[digits_start, self.position)`, validate it, and parse
+    /// the digits in `radix`.
+    fn finish_integer(
+        &mut self,
+        start: usize,
+        digits_start: usize,
+        radix: u32,
+    ) -> Option<Token<'src>> {
+        let digits = &self.input[digits_start..self.position];
+
+        let suffix_start = self.position;
+        self.consume_while(|c| matches!(c, 'u' | 'U' | 'l' | 'L'));
+        let suffix = &self.input[suffix_start..self.position];
+
+        let followed_by_ident_char = self
+            .peek_char()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+        let is_unsigned = suffix.chars().filter(|c| matches!(c, 'u' | 'U')).count();
+        let l_chars: Vec<char> = suffix.chars().filter(|c| matches!(c, 'l' | 'L')).collect();
+        let valid_suffix =
+            is_unsigned <= 1 && matches!(l_chars.as_slice(), [] | [_] | ['l', 'l'] | ['L', 'L']);
+
+        if digits.is_empty() || followed_by_ident_char || !valid_suffix {
+            self.errors.push(LexError {
+                kind: LexErrorKind::InvalidNumber,
+                span: (start, self.position),
+            });
+            return None;
+        }
+
+        match u64::from_str_radix(digits, radix).ok() {
+            Some(value) => Some(Token::Number {
+                value,
+                is_unsigned: is_unsigned > 0,
+                width: match l_chars.len() {
+                    0 => IntWidth::Int,
+                    1 => IntWidth::Long,
+                    _ => IntWidth::LongLong,
+                },
+            }),
+            None => {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::InvalidNumber,
+                    span: (start, self.position),
+                });
+                None
+            }
+        }
+    }
+
+    /// Consume an identifier or keyword starting at the cursor, which must
+    /// already be positioned on an `is_ascii_alphabetic() || '_'` character.
+    fn scan_identifier(&mut self) -> Token<'src> {
+        let start = self.position;
+        self.consume_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        let word = &self.input[start..self.position];
+
+        if let Some(keyword_token) = self.match_keyword(word) {
+            keyword_token
+        } else {
+            self.symbol_table.add(word);
+            Token::Identifier(word)
+        }
+    }
+
+    /// Maximal-munch match of an operator or punctuation token at the
+    /// cursor, trying every two-character operator before falling back to
+    /// single-character punctuation. Returns `None` if the character at the
+    /// cursor isn't part of any operator, leaving the cursor untouched.
+    ///
+    /// Munching stops at two characters, so three-character operators like
+    /// `>>=`/`<<=` come out as `RightShift`/`LeftShift` followed by
+    /// `Assign` rather than a single compound-assign token — there's no
+    /// `Token` variant for them, and this matches the pre-existing
+    /// (regex-based) lexer's behavior.
+    fn scan_operator(&mut self) -> Option<Token<'src>> {
+        let mut chars = self.input[self.position..].chars();
+        let first = chars.next()?;
+        let second = chars.next();
+
+        let two_char = second.and_then(|second| {
+            Some(match (first, second) {
+                ('-', '>') => Token::Arrow,
+                ('=', '=') => Token::Equals,
+                ('!', '=') => Token::NotEquals,
+                ('<', '=') => Token::LessEqual,
+                ('>', '=') => Token::GreaterEqual,
+                ('&', '&') => Token::LogicalAnd,
+                ('|', '|') => Token::LogicalOr,
+                ('+', '+') => Token::Increment,
+                ('-', '-') => Token::Decrement,
+                ('<', '<') => Token::LeftShift,
+                ('>', '>') => Token::RightShift,
+                ('+', '=') => Token::PlusAssign,
+                ('-', '=') => Token::MinusAssign,
+                ('*', '=') => Token::StarAssign,
+                ('/', '=') => Token::SlashAssign,
+                ('%', '=') => Token::PercentAssign,
+                _ => return None,
+            })
+        });
+
+        if let Some(token) = two_char {
+            self.position += first.len_utf8() + second.unwrap().len_utf8();
+            return Some(token);
+        }
+
+        let single = match first {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '=' => Token::Assign,
+            '<' => Token::Less,
+            '>' => Token::Greater,
+            '!' => Token::LogicalNot,
+            '&' => Token::BitwiseAnd,
+            '|' => Token::BitwiseOr,
+            '^' => Token::BitwiseXor,
+            '~' => Token::BitwiseNot,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '?' => Token::Question,
+            ':' => Token::Colon,
+            _ => return None,
+        };
+        self.position += first.len_utf8();
+        Some(single)
+    }
+
+    fn match_keyword(&self, word: &str) -> Option<Token<'src>> {
+        match word {
+            "auto" => Some(Token::Auto),
+            "break" => Some(Token::Break),
+            "case" => Some(Token::Case),
+            "char" => Some(Token::Char),
+            "const" => Some(Token::Const),
+            "continue" => Some(Token::Continue),
+            "default" => Some(Token::Default),
+            "do" => Some(Token::Do),
+            "double" => Some(Token::Double),
+            "else" => Some(Token::Else),
+            "enum" => Some(Token::Enum),
+            "extern" => Some(Token::Extern),
+            "float" => Some(Token::Float),
+            "for" => Some(Token::For),
+            "goto" => Some(Token::Goto),
+            "if" => Some(Token::If),
+            "int" => Some(Token::Int),
+            "long" => Some(Token::Long),
+            "register" => Some(Token::Register),
+            "return" => Some(Token::Return),
+            "short" => Some(Token::Short),
+            "signed" => Some(Token::Signed),
+            "sizeof" => Some(Token::Sizeof),
+            "static" => Some(Token::Static),
+            "struct" => Some(Token::Struct),
+            "switch" => Some(Token::Switch),
+            "typedef" => Some(Token::Typedef),
+            "union" => Some(Token::Union),
+            "unsigned" => Some(Token::Unsigned),
+            "void" => Some(Token::Void),
+            "volatile" => Some(Token::Volatile),
+            "while" => Some(Token::While),
+            _ => None,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek_char() {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.position += ch.len_utf8();
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{IntWidth, Lexer, Span, Token};
+use ropey::Rope;
+use std::ops::Range;
+
+/// A lifetime-erased copy of a [`Token`]. [`RopeLexer`] needs to hold tokens
+/// past the point where the text they were lexed from is edited, so they
+/// can't keep borrowing from it the way [`Token`] normally does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedToken {
+    // Keywords
+    Auto,
+    Break,
+    Case,
+    Char,
+    Const,
+    Continue,
+    Default,
+    Do,
+    Double,
+    Else,
+    Enum,
+    Extern,
+    Float,
+    For,
+    Goto,
+    If,
+    Int,
+    Long,
+    Register,
+    Return,
+    Short,
+    Signed,
+    Sizeof,
+    Static,
+    Struct,
+    Switch,
+    Typedef,
+    Union,
+    Unsigned,
+    Void,
+    Volatile,
+    While,
+
+    // Identifiers and literals
+    Identifier(String),
+    Number {
+        value: u64,
+        is_unsigned: bool,
+        width: IntWidth,
+    },
+    FloatLiteral(f64),
+    CharLiteral(char),
+    String {
+        value: String,
+        has_escape: bool,
+    },
+
+    // Operators and punctuation
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Assign,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    PercentAssign,
+    Increment,
+    Decrement,
+    Equals,
+    NotEquals,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    LogicalAnd,
+    LogicalOr,
+    LogicalNot,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseNot,
+    LeftShift,
+    RightShift,
+
+    // Punctuation
+    Semicolon,
+    Comma,
+    Dot,
+    Arrow,
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Question,
+    Colon,
+
+    // Special tokens
+    EOF,
+}
+
+impl From<Token<'_>> for OwnedToken {
+    fn from(token: Token<'_>) -> Self {
+        match token {
+            Token::Auto => OwnedToken::Auto,
+            Token::Break => OwnedToken::Break,
+            Token::Case => OwnedToken::Case,
+            Token::Char => OwnedToken::Char,
+            Token::Const => OwnedToken::Const,
+            Token::Continue => OwnedToken::Continue,
+            Token::Default => OwnedToken::Default,
+            Token::Do => OwnedToken::Do,
+            Token::Double => OwnedToken::Double,
+            Token::Else => OwnedToken::Else,
+            Token::Enum => OwnedToken::Enum,
+            Token::Extern => OwnedToken::Extern,
+            Token::Float => OwnedToken::Float,
+            Token::For => OwnedToken::For,
+            Token::Goto => OwnedToken::Goto,
+            Token::If => OwnedToken::If,
+            Token::Int => OwnedToken::Int,
+            Token::Long => OwnedToken::Long,
+            Token::Register => OwnedToken::Register,
+            Token::Return => OwnedToken::Return,
+            Token::Short => OwnedToken::Short,
+            Token::Signed => OwnedToken::Signed,
+            Token::Sizeof => OwnedToken::Sizeof,
+            Token::Static => OwnedToken::Static,
+            Token::Struct => OwnedToken::Struct,
+            Token::Switch => OwnedToken::Switch,
+            Token::Typedef => OwnedToken::Typedef,
+            Token::Union => OwnedToken::Union,
+            Token::Unsigned => OwnedToken::Unsigned,
+            Token::Void => OwnedToken::Void,
+            Token::Volatile => OwnedToken::Volatile,
+            Token::While => OwnedToken::While,
+            Token::Identifier(s) => OwnedToken::Identifier(s.to_string()),
+            Token::Number {
+                value,
+                is_unsigned,
+                width,
+            } => OwnedToken::Number {
+                value,
+                is_unsigned,
+                width,
+            },
+            Token::FloatLiteral(f) => OwnedToken::FloatLiteral(f),
+            Token::CharLiteral(c) => OwnedToken::CharLiteral(c),
+            Token::String { value, has_escape } => OwnedToken::String {
+                value: value.into_owned(),
+                has_escape,
+            },
+            Token::Plus => OwnedToken::Plus,
+            Token::Minus => OwnedToken::Minus,
+            Token::Star => OwnedToken::Star,
+            Token::Slash => OwnedToken::Slash,
+            Token::Percent => OwnedToken::Percent,
+            Token::Assign => OwnedToken::Assign,
+            Token::PlusAssign => OwnedToken::PlusAssign,
+            Token::MinusAssign => OwnedToken::MinusAssign,
+            Token::StarAssign => OwnedToken::StarAssign,
+            Token::SlashAssign => OwnedToken::SlashAssign,
+            Token::PercentAssign => OwnedToken::PercentAssign,
+            Token::Increment => OwnedToken::Increment,
+            Token::Decrement => OwnedToken::Decrement,
+            Token::Equals => OwnedToken::Equals,
+            Token::NotEquals => OwnedToken::NotEquals,
+            Token::Less => OwnedToken::Less,
+            Token::Greater => OwnedToken::Greater,
+            Token::LessEqual => OwnedToken::LessEqual,
+            Token::GreaterEqual => OwnedToken::GreaterEqual,
+            Token::LogicalAnd => OwnedToken::LogicalAnd,
+            Token::LogicalOr => OwnedToken::LogicalOr,
+            Token::LogicalNot => OwnedToken::LogicalNot,
+            Token::BitwiseAnd => OwnedToken::BitwiseAnd,
+            Token::BitwiseOr => OwnedToken::BitwiseOr,
+            Token::BitwiseXor => OwnedToken::BitwiseXor,
+            Token::BitwiseNot => OwnedToken::BitwiseNot,
+            Token::LeftShift => OwnedToken::LeftShift,
+            Token::RightShift => OwnedToken::RightShift,
+            Token::Semicolon => OwnedToken::Semicolon,
+            Token::Comma => OwnedToken::Comma,
+            Token::Dot => OwnedToken::Dot,
+            Token::Arrow => OwnedToken::Arrow,
+            Token::LeftParen => OwnedToken::LeftParen,
+            Token::RightParen => OwnedToken::RightParen,
+            Token::LeftBrace => OwnedToken::LeftBrace,
+            Token::RightBrace => OwnedToken::RightBrace,
+            Token::LeftBracket => OwnedToken::LeftBracket,
+            Token::RightBracket => OwnedToken::RightBracket,
+            Token::Question => OwnedToken::Question,
+            Token::Colon => OwnedToken::Colon,
+            Token::EOF => OwnedToken::EOF,
+        }
+    }
+}
+
+/// A lexer backed by a [`Rope`] that re-lexes only the region touched by an
+/// edit, instead of the whole document, so it stays cheap to call on every
+/// keystroke in an editor or language server.
+pub struct RopeLexer {
+    rope: Rope,
+    tokens: Vec<(OwnedToken, Span)>,
+}
+
+impl RopeLexer {
+    pub fn new(rope: Rope) -> Self {
+        let text = rope.to_string();
+        let (tokens, _errors) = crate::lex(&text);
+        let tokens = tokens
+            .into_iter()
+            .map(|(token, span)| (OwnedToken::from(token), span))
+            .collect();
+        RopeLexer { rope, tokens }
+    }
+
+    pub fn tokens(&self) -> &[(OwnedToken, Span)] {
+        &self.tokens
+    }
+
+    /// Splice `new_text` into the byte range `edit`, then re-lex just the
+    /// affected tokens: re-scanning starts at the beginning of the token
+    /// preceding the edit and stops as soon as a freshly-lexed token matches
+    /// an old token past the edit (same kind and span, once the old span is
+    /// shifted by the edit's length delta) — everything from there on is
+    /// reused instead of recomputed.
+    pub fn relex_range(&mut self, edit: Range<usize>, new_text: &str) {
+        let delta = new_text.len() as isize - (edit.end - edit.start) as isize;
+
+        // Rewind to the last real (non-zero-width) token that starts
+        // strictly before the edit — i.e. the token containing or
+        // immediately preceding `edit.start` — rather than just one token
+        // back from the edit boundary. This matters when an edit widens or
+        // narrows a token that runs up to it, such as an unterminated
+        // string/char literal: re-scanning must start at that literal's own
+        // start, not resume mid-token.
+        //
+        // The inequality is strict (`<`, not `<=`) so a token that merely
+        // *starts* at `edit.start` is never picked as the anchor: an edit
+        // sitting right at that boundary can just as easily extend the
+        // token *before* it (e.g. inserting into the middle of what was an
+        // identifier immediately followed by an operator), and only
+        // rewinding to the earlier token lets re-scanning notice that.
+        //
+        // If no such token exists, there is no token to anchor on at all —
+        // the edit may sit inside a comment, preprocessor line, or leading
+        // whitespace, none of which produce tokens, so the cached token list
+        // can't tell us where that construct began. Fall back to rescanning
+        // the whole input from byte 0 rather than guessing.
+        let anchor = self
+            .tokens
+            .iter()
+            .rposition(|(_, span)| span.0 < span.1 && span.0 < edit.start);
+        let resume_from = anchor.unwrap_or(0);
+
+        self.rope
+            .remove(self.rope.byte_to_char(edit.start)..self.rope.byte_to_char(edit.end));
+        self.rope
+            .insert(self.rope.byte_to_char(edit.start), new_text);
+
+        let full_text = self.rope.to_string();
+        // Never resume past the edit, and never past the end of the
+        // (possibly now-shorter) text — a plain deletion to the end of the
+        // input can otherwise try to slice past `full_text`'s new length.
+        let rescan_start = match anchor {
+            Some(i) => self.tokens[i].1 .0.min(edit.start).min(full_text.len()),
+            None => 0,
+        };
+        let mut lexer = Lexer::new(&full_text[rescan_start..]);
+        let mut new_tail = Vec::new();
+
+        loop {
+            let (token, span) = lexer.next_token();
+            let absolute_span = (span.0 + rescan_start, span.1 + rescan_start);
+            let is_eof = token == Token::EOF;
+            let owned = OwnedToken::from(token);
+
+            // A candidate must have started at or after the edit in the
+            // *original* text — otherwise it's a token that overlapped or
+            // preceded the edit, and with delta == 0 its shifted span can
+            // coincide with the first freshly-lexed token even though the
+            // edit changed it.
+            let resync_at = self.tokens[resume_from..]
+                .iter()
+                .position(|(old, old_span)| {
+                    old_span.0 >= edit.end
+                        && shift_span(*old_span, delta) == absolute_span
+                        && *old == owned
+                });
+
+            if let Some(offset) = resync_at {
+                let reused = self.tokens[resume_from + offset..]
+                    .iter()
+                    .cloned()
+                    .map(|(token, span)| (token, shift_span(span, delta)));
+                new_tail.extend(reused);
+                break;
+            }
+
+            new_tail.push((owned, absolute_span));
+            if is_eof {
+                break;
+            }
+        }
+
+        self.tokens.truncate(resume_from);
+        self.tokens.extend(new_tail);
+    }
+}
+
+fn shift_span(span: Span, delta: isize) -> Span {
+    (
+        (span.0 as isize + delta) as usize,
+        (span.1 as isize + delta) as usize,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply `edit`/`new_text` via `relex_range` and check the result against
+    /// a from-scratch lex of the final text, so a stale-token bug shows up as
+    /// a mismatch regardless of which token it affects.
+    fn check_relex(original: &str, edit: Range<usize>, new_text: &str) {
+        let mut rope_lexer = RopeLexer::new(Rope::from_str(original));
+        rope_lexer.relex_range(edit.clone(), new_text);
+
+        let mut expected_text = original.to_string();
+        expected_text.replace_range(edit, new_text);
+        let (expected_tokens, _errors) = crate::lex(&expected_text);
+        let expected: Vec<(OwnedToken, Span)> = expected_tokens
+            .into_iter()
+            .map(|(token, span)| (OwnedToken::from(token), span))
+            .collect();
+
+        assert_eq!(rope_lexer.tokens(), expected);
+    }
+
+    #[test]
+    fn replace_same_length_operator() {
+        check_relex("a + b", 2..3, "-");
+    }
+
+    #[test]
+    fn replace_same_length_identifier() {
+        check_relex("int foo;", 4..7, "bar");
+    }
+
+    #[test]
+    fn replace_same_length_in_repeated_operators() {
+        check_relex("1 + 2 + 3", 2..3, "-");
+    }
+
+    #[test]
+    fn insert_grows_the_edited_token() {
+        check_relex("a + b", 1..1, "bc");
+    }
+
+    #[test]
+    fn delete_shrinks_the_edited_token() {
+        check_relex("int foobar;", 7..10, "");
+    }
+
+    #[test]
+    fn delete_to_end_of_input() {
+        check_relex(" ", 0..1, "");
+    }
+
+    #[test]
+    fn edit_replaces_the_first_token() {
+        check_relex("2b", 0..2, "b<;");
+    }
+
+    #[test]
+    fn insert_extends_unterminated_char_literal() {
+        check_relex("x 'b", 4..4, ";");
+    }
+
+    #[test]
+    fn insert_extends_unterminated_string_literal() {
+        check_relex("x \"ab", 5..5, "cd");
+    }
+
+    #[test]
+    fn delete_shortens_string_literal_past_its_close_quote() {
+        check_relex("\"abc\" + d", 3..6, "");
+    }
+
+    #[test]
+    fn edits_against_full_lex_table() {
+        let cases: &[(&str, Range<usize>, &str)] = &[
+            ("1 + 2 + 3", 0..1, "9"),
+            ("int foo;", 0..3, "long"),
+            ("a.b", 1..1, "0"),
+            ("0x1.8", 0..1, "0"),
+            ("\"a\\nb\"", 2..4, "\\t"),
+            ("'a'", 1..2, "\\n"),
+            ("// hi\nx", 0..5, ""),
+            ("/* c */ x", 3..3, "omment"),
+            ("#define X 1\ny", 0..0, "#define Y 2\n"),
+            ("foo_bar", 3..3, "_"),
+            ("1ull", 0..4, "2ULL"),
+        ];
+
+        for (source, edit, replacement) in cases.iter().cloned() {
+            check_relex(source, edit, replacement);
+        }
+    }
+}